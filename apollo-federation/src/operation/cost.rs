@@ -0,0 +1,262 @@
+use std::collections::HashSet;
+
+use apollo_compiler::Name;
+
+use super::NamedFragments;
+use super::Operation;
+use super::Selection;
+use super::SelectionSet;
+use crate::error::FederationError;
+
+//================================================================================================
+// Cost limiting
+// - depth and complexity caps for operations, enforced before planning/fetching.
+
+/// Caps on how deeply nested and how complex an operation's field selections may be, the way
+/// async-graphql's `SchemaBuilder::limit_depth`/`limit_complexity` bound incoming queries.
+///
+/// A `None` limit leaves the corresponding check disabled.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CostLimits {
+    /// Maximum nesting level of field selections. Fragment spreads and inline fragments are
+    /// transparent to depth; only the fields inside them count.
+    pub max_depth: Option<u32>,
+    /// Maximum weighted sum of field selections, where each field contributes `1 + sum(sub
+    /// selection complexity)`, multiplied by its list multiplier (see [`list_multiplier`]).
+    pub max_complexity: Option<u32>,
+}
+
+/// The accumulated depth and complexity of a selection set.
+#[derive(Debug, Clone, Copy, Default)]
+struct SelectionSetCost {
+    depth: u32,
+    complexity: u64,
+}
+
+/// Reads the list multiplier for a field from its `first`/`last` pagination arguments. Only
+/// applies to fields whose resolved type is actually a list — a scalar/object field that happens
+/// to carry a same-named argument shouldn't have its complexity scaled. Returns `1` when the
+/// field isn't a list, isn't paginated, the argument value isn't a literal integer, or the value
+/// is negative — a negative multiplier would zero out (or invert) the field's own complexity and
+/// everything nested under it, defeating the cap it's supposed to enforce.
+fn list_multiplier(field_data: &super::FieldData) -> u64 {
+    let Some(field_def) = field_data
+        .field_position
+        .try_get(field_data.schema.schema())
+    else {
+        return 1;
+    };
+    if !field_def.ty.is_list() {
+        return 1;
+    }
+    for argument in field_data.arguments.iter() {
+        if argument.name == "first" || argument.name == "last" {
+            if let Some(count) = argument.value.as_i32() {
+                return if count < 0 { 1 } else { count as u64 };
+            }
+        }
+    }
+    1
+}
+
+impl Selection {
+    fn check_cost(
+        &self,
+        named_fragments: &NamedFragments,
+        visited_fragments: &mut HashSet<Name>,
+        path: &mut Vec<String>,
+        limits: &CostLimits,
+    ) -> Result<SelectionSetCost, FederationError> {
+        match self {
+            Selection::Field(field) => {
+                let field_data = field.field.data();
+                path.push(field.field.response_name().to_string());
+
+                let sub_cost = match &field.selection_set {
+                    Some(selection_set) => {
+                        selection_set.check_cost(named_fragments, visited_fragments, path, limits)?
+                    }
+                    None => SelectionSetCost::default(),
+                };
+
+                let cost = SelectionSetCost {
+                    depth: sub_cost.depth.saturating_add(1),
+                    // Saturating rather than raw arithmetic: an adversarial query can easily
+                    // nest a handful of `first: 2000000000` list fields deep enough to overflow
+                    // `u64`, which would either panic (debug) or wrap under `max_complexity`
+                    // (release) and let the query straight through. Saturating at `u64::MAX`
+                    // guarantees it instead reads as "exceeds the limit".
+                    complexity: sub_cost
+                        .complexity
+                        .saturating_add(1)
+                        .saturating_mul(list_multiplier(field_data)),
+                };
+                check_limits(&cost, path, limits)?;
+
+                path.pop();
+                Ok(cost)
+            }
+            Selection::FragmentSpread(fragment_spread) => {
+                let fragment_data = fragment_spread.spread.data();
+                let fragment_name = &fragment_data.fragment_name;
+                if !visited_fragments.insert(fragment_name.clone()) {
+                    return Err(FederationError::internal(format!(
+                        "Cyclic fragment spread on `{fragment_name}` detected at field path `{}`",
+                        path.join(".")
+                    )));
+                }
+                let Some(fragment_def) = named_fragments.get(fragment_name) else {
+                    return Err(FederationError::internal(format!(
+                        "Fragment name not found in the given set: {fragment_name}"
+                    )));
+                };
+                let sub_cost =
+                    fragment_def
+                        .selection_set
+                        .check_cost(named_fragments, visited_fragments, path, limits)?;
+                visited_fragments.remove(fragment_name);
+
+                // Fragment spreads pass through without incrementing depth; their sub-selection
+                // was already checked against `limits` while computing `sub_cost`.
+                Ok(sub_cost)
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                // Same as fragment spreads: no depth of their own, just a pass-through.
+                inline_fragment
+                    .selection_set
+                    .check_cost(named_fragments, visited_fragments, path, limits)
+            }
+        }
+    }
+}
+
+impl SelectionSet {
+    fn check_cost(
+        &self,
+        named_fragments: &NamedFragments,
+        visited_fragments: &mut HashSet<Name>,
+        path: &mut Vec<String>,
+        limits: &CostLimits,
+    ) -> Result<SelectionSetCost, FederationError> {
+        let mut aggregate = SelectionSetCost::default();
+        for selection in self.iter() {
+            let cost = selection.check_cost(named_fragments, visited_fragments, path, limits)?;
+            aggregate.depth = aggregate.depth.max(cost.depth);
+            aggregate.complexity = aggregate.complexity.saturating_add(cost.complexity);
+        }
+        Ok(aggregate)
+    }
+}
+
+fn check_limits(
+    cost: &SelectionSetCost,
+    path: &[String],
+    limits: &CostLimits,
+) -> Result<(), FederationError> {
+    if let Some(max_depth) = limits.max_depth {
+        if cost.depth > max_depth {
+            return Err(FederationError::internal(format!(
+                "Operation exceeds maximum depth of {max_depth} at field path `{}`",
+                path.join(".")
+            )));
+        }
+    }
+    if let Some(max_complexity) = limits.max_complexity {
+        if cost.complexity > max_complexity as u64 {
+            return Err(FederationError::internal(format!(
+                "Operation exceeds maximum complexity of {max_complexity} at field path `{}`",
+                path.join(".")
+            )));
+        }
+    }
+    Ok(())
+}
+
+impl Operation {
+    /// Rejects the operation if its selection nesting or weighted complexity exceeds the
+    /// configured `limits`, the way async-graphql's `SchemaBuilder::limit_depth`/
+    /// `limit_complexity` reject queries at execution time. Letting operators cap these before
+    /// planning/fetching avoids doing expensive work for abusive queries.
+    pub fn check_cost(&self, limits: &CostLimits) -> Result<(), FederationError> {
+        if limits.max_depth.is_none() && limits.max_complexity.is_none() {
+            return Ok(());
+        }
+        let mut visited_fragments = HashSet::new();
+        let mut path = Vec::new();
+        let aggregate = self.selection_set.check_cost(
+            &self.named_fragments,
+            &mut visited_fragments,
+            &mut path,
+            limits,
+        )?;
+        // The `Field` arm of `Selection::check_cost` checks each field's own sub-tree against
+        // `limits` as it unwinds, but nothing checks the root aggregate: a bunch of individually
+        // cheap top-level fields/fragment spreads could still sum past `max_complexity`.
+        check_limits(&aggregate, &path, limits)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_limits_rejects_cost_beyond_either_bound() {
+        let within_bounds = SelectionSetCost {
+            depth: 3,
+            complexity: 10,
+        };
+        let limits = CostLimits {
+            max_depth: Some(5),
+            max_complexity: Some(20),
+        };
+        assert!(check_limits(&within_bounds, &[], &limits).is_ok());
+
+        let too_deep = SelectionSetCost {
+            depth: 6,
+            complexity: 10,
+        };
+        assert!(check_limits(&too_deep, &[], &limits).is_err());
+
+        let too_complex = SelectionSetCost {
+            depth: 3,
+            complexity: 21,
+        };
+        assert!(check_limits(&too_complex, &[], &limits).is_err());
+    }
+
+    #[test]
+    fn check_limits_disabled_when_limit_is_none() {
+        let cost = SelectionSetCost {
+            depth: u32::MAX,
+            complexity: u64::MAX,
+        };
+        let limits = CostLimits {
+            max_depth: None,
+            max_complexity: None,
+        };
+        assert!(check_limits(&cost, &[], &limits).is_ok());
+    }
+
+    #[test]
+    fn complexity_math_saturates_instead_of_overflowing() {
+        // A handful of nested list fields with a huge literal `first`/`last` would overflow
+        // plain `u64` arithmetic; saturating math must cap at `u64::MAX` instead of wrapping
+        // around to a small value that would slip under `max_complexity`.
+        let nearly_maxed = u64::MAX - 1;
+        let multiplier = 2_000_000_000u64;
+        let complexity = nearly_maxed.saturating_add(1).saturating_mul(multiplier);
+        assert_eq!(complexity, u64::MAX);
+
+        let limits = CostLimits {
+            max_depth: None,
+            max_complexity: Some(1_000_000),
+        };
+        let cost = SelectionSetCost {
+            depth: 1,
+            complexity,
+        };
+        assert!(check_limits(&cost, &[], &limits).is_err());
+    }
+}
@@ -7,16 +7,34 @@ use crate::schema::position::CompositeTypeDefinitionPosition;
 use crate::schema::ValidFederationSchema;
 
 // `debug_check`: a debug-only sanity check.
-// - Executes an expression `$result` that returns a `Result<(), E>` and returns the error if the
-// result is an Err.
+// - Calls `$obj.$method($($arg),*, ValidationMode::Fast)` and panics (in debug builds only) if it
+// returns any violation. Always forces `Fast`, since this is meant to be a cheap sanity check, not
+// a full diagnostic pass; it also sidesteps `is_well_formed`'s `Vec<FederationError>` error type,
+// since there's nothing here that needs to propagate it.
 macro_rules! debug_check {
-    ($result: expr) => {
-        debug_assert_eq!((), $result?);
+    ($obj: expr, $method: ident ($($arg: expr),* $(,)?)) => {
+        debug_assert!($obj.$method($($arg),*, ValidationMode::Fast).is_ok());
     };
 }
 
 pub(crate) use debug_check;
 
+/// How thoroughly [`Operation::is_well_formed`] (and friends) should check an operation,
+/// mirroring async-graphql's `ValidationMode`.
+///
+/// - `Fast` stops at the first violation and skips the more expensive cross-reference checks
+///   (e.g. checking a resolved fragment definition's schema/type-condition against the spread
+///   that referenced it) — but it still resolves every fragment spread against
+///   `named_fragments` and reports a missing one, since that check is cheap. This is what
+///   [`debug_check`] uses, since it just wants a cheap sanity check.
+/// - `Full` keeps going and collects every violation it finds, so tooling can surface all
+///   structural problems in one pass instead of fixing them one error at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    Fast,
+    Full,
+}
+
 //================================================================================================
 // Well-formedness checks
 // - structural invariant checks for operations.
@@ -27,111 +45,181 @@ impl Selection {
         schema: &ValidFederationSchema,
         named_fragments: &NamedFragments,
         parent_type: &CompositeTypeDefinitionPosition,
-    ) -> Result<(), FederationError> {
+        mode: ValidationMode,
+    ) -> Result<(), Vec<FederationError>> {
+        let mut errors = Vec::new();
+        macro_rules! check {
+            ($cond: expr, $err: expr) => {
+                if !$cond {
+                    errors.push($err);
+                    if mode == ValidationMode::Fast {
+                        return Err(errors);
+                    }
+                }
+            };
+        }
+
         match self {
             Selection::Field(field) => {
                 let field_data = field.field.data();
-                if field_data.schema != *schema {
-                    return Err(FederationError::internal(format!(
+                check!(
+                    field_data.schema == *schema,
+                    FederationError::internal(format!(
                         "Schema mismatch: expected {:?}, got {:?}",
                         schema, field_data.schema
-                    )));
-                }
-                if field_data.field_position.try_get(schema.schema()).is_none() {
-                    return Err(FederationError::internal(format!(
-                        "Field not found: {field}",
-                    )));
-                }
+                    ))
+                );
+                check!(
+                    field_data.field_position.try_get(schema.schema()).is_some(),
+                    FederationError::internal(format!("Field not found: {field}",))
+                );
                 if let Some(selection_set) = &field.selection_set {
-                    let base_type = field_data.output_base_type()?;
-                    let sub_selection_set_type = base_type.try_into()?;
-                    if selection_set.type_position != sub_selection_set_type {
-                        return Err(FederationError::internal(format!(
-                            "Selection set type position mismatch: expected {:?}, got {:?}",
-                            sub_selection_set_type, selection_set.type_position
-                        )));
+                    let sub_selection_set_type =
+                        (|| -> Result<_, FederationError> { field_data.output_base_type()?.try_into() })();
+                    match sub_selection_set_type {
+                        Ok(sub_selection_set_type) => {
+                            check!(
+                                selection_set.type_position == sub_selection_set_type,
+                                FederationError::internal(format!(
+                                    "Selection set type position mismatch: expected {:?}, got {:?}",
+                                    sub_selection_set_type, selection_set.type_position
+                                ))
+                            );
+                        }
+                        Err(error) => {
+                            errors.push(error);
+                            if mode == ValidationMode::Fast {
+                                return Err(errors);
+                            }
+                        }
+                    }
+                    if let Err(sub_errors) =
+                        selection_set.is_well_formed(schema, named_fragments, mode)
+                    {
+                        errors.extend(sub_errors);
+                        if mode == ValidationMode::Fast {
+                            return Err(errors);
+                        }
                     }
-                    selection_set.is_well_formed(schema, named_fragments)?;
                 }
-                Ok(())
             }
             Selection::FragmentSpread(fragment_spread) => {
                 let fragment_data = fragment_spread.spread.data();
-                if fragment_data.schema != *schema {
-                    return Err(FederationError::internal(format!(
+                check!(
+                    fragment_data.schema == *schema,
+                    FederationError::internal(format!(
                         "Schema mismatch: expected {:?}, got {:?}",
                         schema, fragment_data.schema
-                    )));
-                }
+                    ))
+                );
 
                 // Note: `fragment_spread.selection_set` should be rebased to the `schema` (either
                 // supergraph or subgraph).
-                if fragment_data.type_condition_position
-                    != fragment_spread.selection_set.type_position
-                {
-                    return Err(FederationError::internal(format!(
+                check!(
+                    fragment_data.type_condition_position
+                        == fragment_spread.selection_set.type_position,
+                    FederationError::internal(format!(
                         "Fragment's type-condition ({:?}) and the type of its sub-selection-set ({:?}) mismatch.",
                         fragment_data.type_condition_position,
                         fragment_spread.selection_set.type_position
-                    )));
-                }
-                fragment_spread
-                    .selection_set
-                    .is_well_formed(schema, named_fragments)?;
-
-                let Some(fragment_def) = named_fragments.get(&fragment_data.fragment_name) else {
-                    return Err(FederationError::internal(format!(
-                        "Fragment name not found in the given set: {}",
-                        fragment_data.fragment_name
-                    )));
-                };
-                // Note: `named_fragments` should be rebased to the `schema` (either supergraph or subgraph).
-                if fragment_def.schema != *schema {
-                    return Err(FederationError::internal(format!(
-                        "Fragment definition's schema mismatch: expected {:?}, got {:?}",
-                        schema, fragment_def.schema
-                    )));
-                }
-                if fragment_def.type_condition_position != fragment_data.type_condition_position {
-                    return Err(FederationError::internal(format!(
-                        "Fragment definition's type-condition mismatch: expected {:?}, got {:?}",
-                        fragment_data.type_condition_position, fragment_def.type_condition_position
-                    )));
+                    ))
+                );
+                if let Err(sub_errors) =
+                    fragment_spread
+                        .selection_set
+                        .is_well_formed(schema, named_fragments, mode)
+                {
+                    errors.extend(sub_errors);
+                    if mode == ValidationMode::Fast {
+                        return Err(errors);
+                    }
                 }
 
-                Ok(())
+                // Resolving the fragment definition at all is cheap and worth checking
+                // unconditionally — even `debug_check!` (which always forces `Fast`) should
+                // catch a fragment spread that references a name absent from `named_fragments`.
+                // Cross-checking the resolved definition's schema/type-condition against the
+                // spread is the more expensive part, so that's what `Fast` mode skips once the
+                // cheap checks above have already passed.
+                match named_fragments.get(&fragment_data.fragment_name) {
+                    None => {
+                        errors.push(FederationError::internal(format!(
+                            "Fragment name not found in the given set: {}",
+                            fragment_data.fragment_name
+                        )));
+                        if mode == ValidationMode::Fast {
+                            return Err(errors);
+                        }
+                    }
+                    Some(fragment_def) => {
+                        if mode == ValidationMode::Full {
+                            // Note: `named_fragments` should be rebased to the `schema` (either
+                            // supergraph or subgraph).
+                            check!(
+                                fragment_def.schema == *schema,
+                                FederationError::internal(format!(
+                                    "Fragment definition's schema mismatch: expected {:?}, got {:?}",
+                                    schema, fragment_def.schema
+                                ))
+                            );
+                            check!(
+                                fragment_def.type_condition_position
+                                    == fragment_data.type_condition_position,
+                                FederationError::internal(format!(
+                                    "Fragment definition's type-condition mismatch: expected {:?}, got {:?}",
+                                    fragment_data.type_condition_position, fragment_def.type_condition_position
+                                ))
+                            );
+                        }
+                    }
+                }
                 // Note: fragment_data.type_condition_position and the parent type do not have to have
                 // non-empty intersection to be well-formed. It would be an extra check.
             }
             Selection::InlineFragment(inline_fragment) => {
                 let fragment_data = inline_fragment.inline_fragment.data();
-                if fragment_data.schema != *schema {
-                    return Err(FederationError::internal(format!(
+                check!(
+                    fragment_data.schema == *schema,
+                    FederationError::internal(format!(
                         "Schema mismatch: expected {:?}, got {:?}",
                         schema, fragment_data.schema
-                    )));
-                }
-                if fragment_data.parent_type_position != *parent_type {
-                    return Err(FederationError::internal(format!(
+                    ))
+                );
+                check!(
+                    fragment_data.parent_type_position == *parent_type,
+                    FederationError::internal(format!(
                         "Parent type mismatch: expected {:?}, got {:?}",
                         parent_type, fragment_data.parent_type_position
-                    )));
-                }
-                if fragment_data.casted_type() != inline_fragment.selection_set.type_position {
-                    return Err(FederationError::internal(format!(
+                    ))
+                );
+                check!(
+                    fragment_data.casted_type() == inline_fragment.selection_set.type_position,
+                    FederationError::internal(format!(
                         "Inline fragment's casted-type ({:?}) and the type of its sub-selection-set ({:?}) mismatch.",
                         fragment_data.casted_type(),
                         inline_fragment.selection_set.type_position
-                    )));
+                    ))
+                );
+                if let Err(sub_errors) =
+                    inline_fragment
+                        .selection_set
+                        .is_well_formed(schema, named_fragments, mode)
+                {
+                    errors.extend(sub_errors);
+                    if mode == ValidationMode::Fast {
+                        return Err(errors);
+                    }
                 }
-                inline_fragment
-                    .selection_set
-                    .is_well_formed(schema, named_fragments)?;
-                Ok(())
                 // Note: fragment_data.type_condition_position and the parent type do not have to have
                 // non-empty intersection to be well-formed. It would be an extra check.
             }
         }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 }
 
@@ -140,30 +228,117 @@ impl SelectionSet {
         &self,
         schema: &ValidFederationSchema,
         named_fragments: &NamedFragments,
-    ) -> Result<(), FederationError> {
+        mode: ValidationMode,
+    ) -> Result<(), Vec<FederationError>> {
+        let mut errors = Vec::new();
         if self.schema != *schema {
-            return Err(FederationError::internal(format!(
+            errors.push(FederationError::internal(format!(
                 "Schema mismatch: expected {:?}, got {:?}",
                 schema, self.schema
             )));
+            if mode == ValidationMode::Fast {
+                return Err(errors);
+            }
         }
         for selection in self.iter() {
-            selection.is_well_formed(schema, named_fragments, &self.type_position)?;
+            if let Err(sub_errors) =
+                selection.is_well_formed(schema, named_fragments, &self.type_position, mode)
+            {
+                errors.extend(sub_errors);
+                if mode == ValidationMode::Fast {
+                    return Err(errors);
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
         }
-        Ok(())
     }
 }
 
 impl Operation {
-    pub fn is_well_formed(&self, schema: &ValidFederationSchema) -> Result<(), FederationError> {
+    pub fn is_well_formed(
+        &self,
+        schema: &ValidFederationSchema,
+        mode: ValidationMode,
+    ) -> Result<(), Vec<FederationError>> {
+        let mut errors = Vec::new();
         if self.schema != *schema {
-            return Err(FederationError::internal(format!(
+            errors.push(FederationError::internal(format!(
                 "Schema mismatch: expected {:?}, got {:?}",
                 schema, self.schema
             )));
+            if mode == ValidationMode::Fast {
+                return Err(errors);
+            }
+        }
+        if let Err(sub_errors) =
+            self.selection_set
+                .is_well_formed(schema, &self.named_fragments, mode)
+        {
+            errors.extend(sub_errors);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use apollo_compiler::ExecutableDocument;
+    use apollo_compiler::Schema;
+
+    use super::*;
+
+    const SDL: &str = r#"
+        type Query {
+            books: [Book!]!
+        }
+
+        type Book {
+            title: String!
         }
-        self.selection_set
-            .is_well_formed(schema, &self.named_fragments)?;
-        Ok(())
+    "#;
+
+    fn valid_schema() -> ValidFederationSchema {
+        let schema = Schema::parse_and_validate(SDL, "schema.graphql").unwrap();
+        ValidFederationSchema::new(schema).unwrap()
     }
-}
\ No newline at end of file
+
+    fn federation_operation(schema: &ValidFederationSchema, query: &str) -> Operation {
+        let document = ExecutableDocument::parse(schema.schema(), query, "query.graphql").unwrap();
+        let operation = document.operations.get(None).unwrap();
+        let named_fragments = NamedFragments::new(&document.fragments, schema);
+        crate::operation::normalize_operation(schema, operation, &named_fragments).unwrap()
+    }
+
+    #[test]
+    fn fast_mode_still_reports_an_unresolved_fragment_spread() {
+        let schema = valid_schema();
+        let operation = federation_operation(&schema, "query { books { ...MissingFragment } }");
+
+        // Even in `Fast` mode, resolving a fragment spread against `named_fragments` is cheap
+        // enough to stay unconditional — only the schema/type-condition cross-reference of an
+        // already-resolved fragment definition is skipped.
+        assert!(operation
+            .is_well_formed(&schema, ValidationMode::Fast)
+            .is_err());
+    }
+
+    #[test]
+    fn well_formed_operation_passes_both_modes() {
+        let schema = valid_schema();
+        let operation = federation_operation(&schema, "query { books { title } }");
+
+        assert!(operation
+            .is_well_formed(&schema, ValidationMode::Fast)
+            .is_ok());
+        assert!(operation
+            .is_well_formed(&schema, ValidationMode::Full)
+            .is_ok());
+    }
+}
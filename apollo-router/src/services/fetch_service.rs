@@ -3,17 +3,25 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::task::Poll;
+use std::time::Duration;
 
+use apollo_compiler::schema::ExtendedType;
 use apollo_compiler::validation::Valid;
+use apollo_federation::operation::NamedFragments;
+use apollo_federation::operation::ValidationMode;
+use apollo_federation::schema::ValidFederationSchema;
 use apollo_federation::sources::connect::Connectors;
 // use apollo_federation::sources::connect::Connectors;
 use futures::future::BoxFuture;
 use tower::BoxError;
 use tower::ServiceExt;
+use tracing::Instrument;
 
 use super::fetch::BoxService;
 use super::new_service::ServiceFactory;
 use super::SubgraphRequest;
+use crate::error::FetchError;
+use crate::graphql::OperationKind;
 use crate::graphql::Request as GraphQLRequest;
 use crate::http_ext;
 use crate::json_ext::Object;
@@ -30,6 +38,57 @@ use crate::services::FetchResponse;
 use crate::services::SubgraphServiceFactory;
 use crate::spec::Schema;
 
+/// Retry policy for a single subgraph's fetches: how many times to retry, how long to wait
+/// between attempts, and which operation kinds are eligible. Modeled on the exponential backoff
+/// with jitter that `backoff` (used for rover's network calls) implements.
+#[derive(Debug, Clone)]
+pub(crate) struct SubgraphRetryConfig {
+    /// Total number of attempts, including the first. `1` disables retrying.
+    pub(crate) max_attempts: u32,
+    pub(crate) initial_interval: Duration,
+    pub(crate) max_interval: Duration,
+    pub(crate) multiplier: f64,
+    /// Mutations aren't idempotent in general, so they're only retried when this is set.
+    pub(crate) retry_mutations: bool,
+}
+
+impl Default for SubgraphRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_interval: Duration::from_millis(100),
+            max_interval: Duration::from_secs(5),
+            multiplier: 2.0,
+            retry_mutations: false,
+        }
+    }
+}
+
+/// Whether a fetch failure looks transient (connection error, 5xx, timeout) and is therefore
+/// worth retrying, as opposed to a permanent failure (4xx, malformed query) that would just fail
+/// again the same way.
+fn is_retryable_error(error: &BoxError) -> bool {
+    match error.downcast_ref::<FetchError>() {
+        Some(FetchError::SubrequestHttpError { status_code, .. }) => {
+            status_code.map(|code| code >= 500).unwrap_or(true)
+        }
+        Some(_) => false,
+        // Errors that didn't come from our own subgraph-call machinery (connection refused, DNS
+        // failures, timeouts bubbling up from the underlying HTTP client, ...) are assumed to be
+        // transient.
+        None => true,
+    }
+}
+
+/// Computes the delay before the next attempt, using full jitter: a random duration between
+/// zero and `initial_interval * multiplier ^ (attempt - 1)`, capped at `max_interval`.
+fn backoff_with_jitter(config: &SubgraphRetryConfig, attempt: u32) -> Duration {
+    let exponent = attempt.saturating_sub(1) as i32;
+    let uncapped = config.initial_interval.as_secs_f64() * config.multiplier.powi(exponent);
+    let capped = uncapped.min(config.max_interval.as_secs_f64()).max(0.0);
+    Duration::from_secs_f64(rand::random::<f64>() * capped)
+}
+
 #[derive(Clone)]
 pub(crate) struct FetchService {
     pub(crate) service_factory: Arc<SubgraphServiceFactory>,
@@ -37,6 +96,358 @@ pub(crate) struct FetchService {
     pub(crate) subgraph_schemas: Arc<HashMap<String, Arc<Valid<apollo_compiler::Schema>>>>,
     pub(crate) _subscription_config: Option<SubscriptionConfig>,
     pub(crate) connectors: Connectors,
+    /// When set, responses returned by subgraphs are checked against the subgraph's own schema
+    /// and the operation that was sent, and mismatches are rejected instead of forwarded
+    /// downstream.
+    pub(crate) validate_subgraph_responses: bool,
+    /// Per-subgraph retry policy, keyed by subgraph name. Subgraphs without an entry fall back
+    /// to `default_retry_config`.
+    pub(crate) retry_configs: Arc<HashMap<String, SubgraphRetryConfig>>,
+    pub(crate) default_retry_config: SubgraphRetryConfig,
+}
+
+/// Checks that a leaf (non-composite) field's value has the shape its type demands: lists line
+/// up with `Type::List`/`Type::NonNullList` nesting, and enum-typed leaves are encoded as
+/// strings rather than arbitrary JSON (an object at a leaf position is never valid, regardless of
+/// the scalar).
+fn check_leaf_shape(
+    value: &Value,
+    ty: &apollo_compiler::executable::Type,
+    schema: &apollo_compiler::Schema,
+    path: &[String],
+) -> Result<(), String> {
+    use apollo_compiler::executable::Type;
+
+    if matches!(value, Value::Null) {
+        return Ok(());
+    }
+    match ty {
+        Type::List(inner) | Type::NonNullList(inner) => {
+            let Value::Array(items) = value else {
+                return Err(format!(
+                    "expected a list at `{}`, found `{value}`",
+                    path.join(".")
+                ));
+            };
+            for item in items {
+                check_leaf_shape(item, inner, schema, path)?;
+            }
+            Ok(())
+        }
+        Type::Named(name) | Type::NonNullNamed(name) => {
+            if matches!(value, Value::Object(_)) {
+                return Err(format!(
+                    "expected a scalar/enum value at `{}`, found an object",
+                    path.join(".")
+                ));
+            }
+            if matches!(schema.types.get(name), Some(ExtendedType::Enum(_)))
+                && !matches!(value, Value::String(_))
+            {
+                return Err(format!(
+                    "expected a string for enum `{name}` at `{}`, found `{value}`",
+                    path.join(".")
+                ));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Whether `object` (identified by its `__typename`, if the response included one) satisfies a
+/// fragment's `type_condition` — either directly, or by implementing/being a member of it when
+/// the condition names an interface or union. Without a `__typename` to check there's nothing to
+/// disambiguate on, so the fragment is assumed to apply rather than over-rejecting a response
+/// that simply didn't request it.
+fn object_satisfies_type_condition(
+    object: &crate::json_ext::Object,
+    type_condition: &apollo_compiler::Name,
+    schema: &apollo_compiler::Schema,
+) -> bool {
+    let Some(Value::String(typename)) = object.get("__typename") else {
+        return true;
+    };
+    typename.as_str() == type_condition.as_str()
+        || schema.is_subtype(type_condition.as_str(), typename.as_str())
+}
+
+/// Walks the fields of a single response object against `selection_set`, recursing through
+/// fragment spreads/inline fragments *without* re-entering [`check_value_conforms_to_selection_set`]
+/// for the same object — otherwise a fragment's own (necessarily partial) selection set would be
+/// mistaken for the full set of keys that object is allowed to have. `expected_keys` accumulates
+/// every response key this `object` is allowed to carry, across all branches, so the caller can
+/// check for extra keys exactly once per object.
+fn check_fields(
+    object: &crate::json_ext::Object,
+    selection_set: &apollo_compiler::executable::SelectionSet,
+    document: &apollo_compiler::ExecutableDocument,
+    schema: &apollo_compiler::Schema,
+    path: &mut Vec<String>,
+    expected_keys: &mut std::collections::HashSet<String>,
+) -> Result<(), String> {
+    for selection in &selection_set.selections {
+        match selection {
+            apollo_compiler::executable::Selection::Field(field) => {
+                let response_key = field.response_key().to_string();
+                expected_keys.insert(response_key.clone());
+                path.push(response_key.clone());
+                match object.get(response_key.as_str()) {
+                    Some(field_value) => {
+                        if !field.selection_set.selections.is_empty() {
+                            match field_value {
+                                Value::Null => {}
+                                Value::Array(items) => {
+                                    for item in items {
+                                        check_value_conforms_to_selection_set(
+                                            item,
+                                            &field.selection_set,
+                                            document,
+                                            schema,
+                                            path,
+                                        )?;
+                                    }
+                                }
+                                _ => check_value_conforms_to_selection_set(
+                                    field_value,
+                                    &field.selection_set,
+                                    document,
+                                    schema,
+                                    path,
+                                )?,
+                            }
+                        } else {
+                            check_leaf_shape(field_value, field.ty(), schema, path)?;
+                        }
+                    }
+                    None if field.ty().is_non_null() => {
+                        return Err(format!(
+                            "non-null field `{}` is missing from the response",
+                            path.join(".")
+                        ));
+                    }
+                    None => {}
+                }
+                path.pop();
+            }
+            apollo_compiler::executable::Selection::FragmentSpread(spread) => {
+                let Some(fragment) = document.fragments.get(&spread.fragment_name) else {
+                    return Err(format!(
+                        "fragment `{}` referenced at `{}` is not defined",
+                        spread.fragment_name,
+                        path.join(".")
+                    ));
+                };
+                // Skip the branch entirely when `object` is a concrete type that this
+                // fragment's type condition doesn't cover — e.g. `... on Book { title }` on an
+                // interface field that actually resolved to `Movie` shouldn't be checked against
+                // `Book`'s (possibly non-null) fields at all.
+                if object_satisfies_type_condition(object, &fragment.type_condition, schema) {
+                    check_fields(
+                        object,
+                        &fragment.selection_set,
+                        document,
+                        schema,
+                        path,
+                        expected_keys,
+                    )?;
+                }
+            }
+            apollo_compiler::executable::Selection::InlineFragment(inline) => {
+                let applies = match &inline.type_condition {
+                    Some(type_condition) => {
+                        object_satisfies_type_condition(object, type_condition, schema)
+                    }
+                    None => true,
+                };
+                if applies {
+                    check_fields(
+                        object,
+                        &inline.selection_set,
+                        document,
+                        schema,
+                        path,
+                        expected_keys,
+                    )?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Walks a subgraph response's `value` alongside the `selection_set` that produced it, making
+/// sure every requested field alias/name is present (or legitimately absent, for nullable
+/// fields), that object/list/leaf shapes line up, and that the response doesn't carry keys that
+/// were never requested. This is deliberately the same kind of structural walk as
+/// [`apollo_federation::operation::Selection::is_well_formed`], just applied to the response data
+/// rather than to the operation alone.
+fn check_value_conforms_to_selection_set(
+    value: &Value,
+    selection_set: &apollo_compiler::executable::SelectionSet,
+    document: &apollo_compiler::ExecutableDocument,
+    schema: &apollo_compiler::Schema,
+    path: &mut Vec<String>,
+) -> Result<(), String> {
+    if matches!(value, Value::Null) {
+        return Ok(());
+    }
+    let Value::Object(object) = value else {
+        return Err(format!(
+            "expected an object at `{}`, found `{value}`",
+            path.join(".")
+        ));
+    };
+
+    let mut expected_keys = std::collections::HashSet::new();
+    check_fields(
+        object,
+        selection_set,
+        document,
+        schema,
+        path,
+        &mut expected_keys,
+    )?;
+
+    for key in object.keys() {
+        if !expected_keys.contains(key.as_str()) {
+            return Err(format!(
+                "response contains key `{key}` at `{}` that wasn't requested",
+                path.join(".")
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Builds a federation-internal [`apollo_federation::operation::Operation`] for `operation`
+/// against `schema` and runs it through the real [`is_well_formed`](
+/// apollo_federation::operation::Operation::is_well_formed) machinery, the same structural check
+/// planning relies on elsewhere. This is belt-and-suspenders alongside
+/// [`check_value_conforms_to_selection_set`]: that walk checks the *response data* against the
+/// operation; this checks that the *operation itself* (as reconstructed for validation) is still
+/// internally consistent, which would catch a schema drift that corrupted both in the same way.
+fn check_operation_is_well_formed(
+    schema: &ValidFederationSchema,
+    operation: &apollo_compiler::executable::Operation,
+    named_fragments: &NamedFragments,
+) -> Result<(), String> {
+    let federation_operation =
+        apollo_federation::operation::normalize_operation(schema, operation, named_fragments)
+            .map_err(|error| format!("could not normalize the executed operation: {error}"))?;
+    federation_operation
+        .is_well_formed(schema, ValidationMode::Full)
+        .map_err(|errors| {
+            errors
+                .into_iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+}
+
+/// Re-parses the operation that was sent to `subgraph_name` against that subgraph's own schema,
+/// then checks that `response_data` actually has the shape the operation asked for. Returns a
+/// [`FetchError`] naming the subgraph and the offending field path on the first mismatch.
+fn validate_subgraph_response(
+    subgraph_name: &str,
+    query: &str,
+    operation_name: Option<&str>,
+    subgraph_schema: &Valid<apollo_compiler::Schema>,
+    response_data: &Value,
+) -> Result<(), FetchError> {
+    let malformed = |reason: String| FetchError::SubrequestMalformedResponse {
+        service: subgraph_name.to_string(),
+        reason,
+    };
+
+    let document =
+        apollo_compiler::ExecutableDocument::parse(subgraph_schema, query, "subgraph_query.graphql")
+            .map_err(|error| {
+                malformed(format!(
+                    "could not reconstruct the executed operation for response validation: {error}"
+                ))
+            })?;
+    let operation = document
+        .operations
+        .get(operation_name)
+        .map_err(|error| malformed(format!("could not find the executed operation: {error}")))?;
+
+    if let Ok(valid_schema) = ValidFederationSchema::new(subgraph_schema.clone()) {
+        let named_fragments = NamedFragments::new(&document.fragments, &valid_schema);
+        if let Err(reason) =
+            check_operation_is_well_formed(&valid_schema, operation, &named_fragments)
+        {
+            return Err(malformed(reason));
+        }
+    }
+
+    let mut path = Vec::new();
+    check_value_conforms_to_selection_set(
+        response_data,
+        &operation.selection_set,
+        &document,
+        subgraph_schema,
+        &mut path,
+    )
+    .map_err(malformed)
+}
+
+/// A single Apollo Connector (REST) fetch to run through [`ConnectorService`]: the query-plan
+/// node describing the REST call (the fetch node's `Connect` source node), its connector service
+/// name (taken from [`RestFetchNode::connector_service_name`] at the call site), plus the entity
+/// data/paths it's resolving — the connector equivalent of a
+/// [`SubgraphRequest`]. Generic over the inverted-paths type so this doesn't have to name
+/// [`Variables::inverted_paths`]'s concrete type.
+struct ConnectorRequest<'a, P> {
+    connect_node: &'a apollo_federation::sources::connect::query_plan::FetchNode,
+    connector_service_name: String,
+    data: Value,
+    paths: P,
+}
+
+/// Drives a single Apollo Connector (REST) fetch end-to-end, the way a boxed subgraph service
+/// drives a regular GraphQL subgraph fetch. Built fresh per fetch from the router's [`Connectors`]
+/// registry, so it always dispatches through [`process_source_node`] (which applies the
+/// connector's input/output rewrites and response mapping) rather than being a discarded side
+/// effect.
+#[derive(Clone)]
+struct ConnectorService {
+    connectors: Connectors,
+}
+
+impl ConnectorService {
+    fn new(connectors: Connectors) -> Self {
+        Self { connectors }
+    }
+}
+
+impl<'a, P> tower::Service<ConnectorRequest<'a, P>> for ConnectorService
+where
+    P: Send + 'a,
+{
+    type Response = FetchResponse;
+    type Error = BoxError;
+    type Future = BoxFuture<'a, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: ConnectorRequest<'a, P>) -> Self::Future {
+        let connectors = self.connectors.clone();
+        let span = tracing::info_span!(
+            "connector_fetch",
+            connector_service = %request.connector_service_name
+        );
+        Box::pin(
+            async move {
+                process_source_node(request.connect_node, connectors, request.data, request.paths)
+                    .await
+                    .map_err(BoxError::from)
+            }
+            .instrument(span),
+        )
+    }
 }
 
 impl tower::Service<FetchRequest> for FetchService {
@@ -140,6 +551,8 @@ impl tower::Service<FetchRequest> for FetchService {
             operation.as_serialized()
         };
 
+        let connector_service_name = subgraph_service_name.clone();
+
         let mut subgraph_request = SubgraphRequest::builder()
             .supergraph_request(supergraph_request.clone())
             .subgraph_request(
@@ -170,6 +583,14 @@ impl tower::Service<FetchRequest> for FetchService {
         let current_dir = current_dir.clone();
         let deferred_fetches = deferred_fetches.clone();
         let connectors = self.connectors.clone();
+        let operation_name_for_validation = operation_name.as_ref().map(|n| n.to_string());
+        let validate_subgraph_responses = self.validate_subgraph_responses;
+        let subgraph_schema = self.subgraph_schemas.get(&sns).cloned();
+        let retry_config = self
+            .retry_configs
+            .get(sns.as_str())
+            .cloned()
+            .unwrap_or_else(|| self.default_retry_config.clone());
         // TODO: dont' panic Oo
         let service = sf
             .create(&sns)
@@ -180,25 +601,91 @@ impl tower::Service<FetchRequest> for FetchService {
                 connect_node,
             )) = fetch_node.source_node.as_deref()
             {
-                // TODO: Dispatch into the ConnectorService eventually
-                let _ = process_source_node(connect_node, connectors, data, paths.clone()).await;
+                // Apollo Connector fetches don't go through a GraphQL subgraph at all, so they
+                // skip the subgraph service/retry machinery below entirely and are driven
+                // straight through to a real REST response via `ConnectorService`.
+                let mut connector_service = ConnectorService::new(connectors);
+                let response = connector_service
+                    .oneshot(ConnectorRequest {
+                        connect_node,
+                        connector_service_name: connector_service_name.clone(),
+                        data,
+                        paths,
+                    })
+                    .await?;
+                return Ok(response);
             }
 
-            Ok(FetchNode::subgraph_fetch(
-                service,
-                subgraph_request,
-                &sns,
-                &current_dir,
-                &requires,
-                &output_rewrites,
-                &schema,
-                paths,
-                id,
-                &deferred_fetches,
-                &aqs,
-                variables,
-            )
-            .await)
+            // Only idempotent queries retry by default; mutations need to opt in, since a
+            // retried mutation could apply a side effect twice.
+            let retries_allowed =
+                matches!(operation_kind, OperationKind::Query) || retry_config.retry_mutations;
+            let max_attempts = if retries_allowed {
+                retry_config.max_attempts.max(1)
+            } else {
+                1
+            };
+
+            let mut attempt: u32 = 1;
+            let mut next_service = Some(service);
+            let response = loop {
+                // Retries can't reuse a subgraph service that already consumed a request, so
+                // every attempt past the first asks the factory for a brand-new one.
+                let attempt_service = match next_service.take() {
+                    Some(service) => service,
+                    None => sf.create(&sns).expect(
+                        "we already checked that the service exists during planning; qed",
+                    ),
+                };
+                let span = tracing::info_span!("subgraph_fetch", attempt, subgraph = %sns);
+
+                let result = FetchNode::subgraph_fetch(
+                    attempt_service,
+                    subgraph_request.clone(),
+                    &sns,
+                    &current_dir,
+                    &requires,
+                    &output_rewrites,
+                    &schema,
+                    paths.clone(),
+                    id,
+                    &deferred_fetches,
+                    &aqs,
+                    variables.clone(),
+                )
+                .instrument(span)
+                .await;
+
+                match result {
+                    Ok(response) => break response,
+                    Err(error) if attempt < max_attempts && is_retryable_error(&error) => {
+                        tracing::debug!(
+                            subgraph = %sns,
+                            attempt,
+                            error = %error,
+                            "retrying subgraph fetch after a transient failure"
+                        );
+                        tokio::time::sleep(backoff_with_jitter(&retry_config, attempt)).await;
+                        attempt += 1;
+                        continue;
+                    }
+                    Err(error) => return Err(error),
+                }
+            };
+
+            if validate_subgraph_responses {
+                if let Some(subgraph_schema) = subgraph_schema {
+                    validate_subgraph_response(
+                        &sns,
+                        &aqs,
+                        operation_name_for_validation.as_deref(),
+                        &subgraph_schema,
+                        &response.0,
+                    )?;
+                }
+            }
+
+            Ok(response)
         })
     }
 }
@@ -210,6 +697,9 @@ pub(crate) struct FetchServiceFactory {
     pub(crate) subgraph_service_factory: Arc<SubgraphServiceFactory>,
     pub(crate) subscription_config: Option<SubscriptionConfig>,
     pub(crate) connectors: Connectors,
+    pub(crate) validate_subgraph_responses: bool,
+    pub(crate) retry_configs: Arc<HashMap<String, SubgraphRetryConfig>>,
+    pub(crate) default_retry_config: SubgraphRetryConfig,
 }
 
 impl FetchServiceFactory {
@@ -219,6 +709,9 @@ impl FetchServiceFactory {
         subgraph_service_factory: Arc<SubgraphServiceFactory>,
         subscription_config: Option<SubscriptionConfig>,
         connectors: Connectors,
+        validate_subgraph_responses: bool,
+        retry_configs: Arc<HashMap<String, SubgraphRetryConfig>>,
+        default_retry_config: SubgraphRetryConfig,
     ) -> Self {
         Self {
             subgraph_service_factory,
@@ -226,6 +719,9 @@ impl FetchServiceFactory {
             schema,
             subscription_config,
             connectors,
+            validate_subgraph_responses,
+            retry_configs,
+            default_retry_config,
         }
     }
 
@@ -247,7 +743,199 @@ impl ServiceFactory<FetchRequest> for FetchServiceFactory {
             subgraph_schemas: self.subgraph_schemas.clone(),
             _subscription_config: self.subscription_config.clone(),
             connectors: self.connectors.clone(),
+            validate_subgraph_responses: self.validate_subgraph_responses,
+            retry_configs: self.retry_configs.clone(),
+            default_retry_config: self.default_retry_config.clone(),
         }
         .boxed()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use apollo_compiler::executable::Type;
+    use apollo_compiler::Name;
+    use apollo_compiler::Schema;
+
+    use super::*;
+
+    #[test]
+    fn connector_service_is_always_ready() {
+        let mut service = ConnectorService::new(Connectors::default());
+        let mut cx = std::task::Context::from_waker(futures::task::noop_waker_ref());
+        assert!(matches!(
+            tower::Service::<ConnectorRequest<'_, ()>>::poll_ready(&mut service, &mut cx),
+            Poll::Ready(Ok(()))
+        ));
+    }
+
+    #[test]
+    fn http_errors_retry_only_on_5xx_or_missing_status() {
+        let server_error = BoxError::from(FetchError::SubrequestHttpError {
+            status_code: Some(503),
+            service: "books".to_string(),
+            reason: "unavailable".to_string(),
+        });
+        assert!(is_retryable_error(&server_error));
+
+        let client_error = BoxError::from(FetchError::SubrequestHttpError {
+            status_code: Some(400),
+            service: "books".to_string(),
+            reason: "bad request".to_string(),
+        });
+        assert!(!is_retryable_error(&client_error));
+
+        let no_status = BoxError::from(FetchError::SubrequestHttpError {
+            status_code: None,
+            service: "books".to_string(),
+            reason: "connection reset".to_string(),
+        });
+        assert!(is_retryable_error(&no_status));
+    }
+
+    #[test]
+    fn other_fetch_errors_are_not_retried() {
+        let malformed = BoxError::from(FetchError::SubrequestMalformedResponse {
+            service: "books".to_string(),
+            reason: "bad shape".to_string(),
+        });
+        assert!(!is_retryable_error(&malformed));
+    }
+
+    #[test]
+    fn unrecognized_errors_are_assumed_transient() {
+        let error = BoxError::from(std::io::Error::new(std::io::ErrorKind::Other, "boom"));
+        assert!(is_retryable_error(&error));
+    }
+
+    #[test]
+    fn backoff_is_jittered_within_the_capped_exponential_window() {
+        let config = SubgraphRetryConfig {
+            max_attempts: 5,
+            initial_interval: Duration::from_millis(100),
+            max_interval: Duration::from_secs(1),
+            multiplier: 2.0,
+            retry_mutations: false,
+        };
+
+        for attempt in 1..=5 {
+            let delay = backoff_with_jitter(&config, attempt);
+            let window = config
+                .initial_interval
+                .mul_f64(config.multiplier.powi(attempt as i32 - 1))
+                .min(config.max_interval);
+            assert!(delay <= window);
+        }
+    }
+
+    #[test]
+    fn backoff_never_exceeds_max_interval_even_after_many_attempts() {
+        let config = SubgraphRetryConfig {
+            max_attempts: 20,
+            initial_interval: Duration::from_millis(100),
+            max_interval: Duration::from_secs(1),
+            multiplier: 2.0,
+            retry_mutations: false,
+        };
+
+        // A large attempt count would blow past `max_interval` with unclamped exponential
+        // growth; the cap must hold regardless of how many attempts have elapsed.
+        let delay = backoff_with_jitter(&config, 20);
+        assert!(delay <= config.max_interval);
+    }
+
+    const SDL: &str = r#"
+        interface Media {
+            title: String!
+        }
+
+        type Book implements Media {
+            title: String!
+            isbn: String!
+        }
+
+        type Movie implements Media {
+            title: String!
+            director: String!
+        }
+
+        enum Status {
+            ACTIVE
+            INACTIVE
+        }
+
+        type Query {
+            media: [Media!]!
+        }
+    "#;
+
+    fn schema() -> Schema {
+        Schema::parse_and_validate(SDL, "schema.graphql").unwrap()
+    }
+
+    fn object(json: serde_json::Value) -> crate::json_ext::Object {
+        let value: Value = json.into();
+        let Value::Object(object) = value else {
+            panic!("expected a JSON object");
+        };
+        object
+    }
+
+    #[test]
+    fn leaf_shape_rejects_an_object_in_place_of_a_scalar() {
+        let schema = schema();
+        let ty = Type::NonNullNamed(Name::new("String").unwrap());
+        let value: Value = serde_json::json!({ "oops": true }).into();
+        assert!(check_leaf_shape(&value, &ty, &schema, &[]).is_err());
+    }
+
+    #[test]
+    fn leaf_shape_requires_a_string_for_enum_values() {
+        let schema = schema();
+        let ty = Type::NonNullNamed(Name::new("Status").unwrap());
+        let valid: Value = serde_json::json!("ACTIVE").into();
+        let invalid: Value = serde_json::json!(1).into();
+        assert!(check_leaf_shape(&valid, &ty, &schema, &[]).is_ok());
+        assert!(check_leaf_shape(&invalid, &ty, &schema, &[]).is_err());
+    }
+
+    #[test]
+    fn type_condition_matches_own_type_and_implemented_interface_but_not_siblings() {
+        let schema = schema();
+        let movie = Name::new("Movie").unwrap();
+        let media = Name::new("Media").unwrap();
+        let book = Name::new("Book").unwrap();
+        let response_object = object(serde_json::json!({ "__typename": "Movie" }));
+
+        // A `... on Movie` fragment applies to a `Movie` response directly, and a `... on
+        // Media` fragment applies via the interface it implements...
+        assert!(object_satisfies_type_condition(
+            &response_object,
+            &movie,
+            &schema
+        ));
+        assert!(object_satisfies_type_condition(
+            &response_object,
+            &media,
+            &schema
+        ));
+        // ...but a sibling implementor's fragment (`... on Book`) must not be walked against a
+        // `Movie` response, since `Book`'s non-null fields would never be present.
+        assert!(!object_satisfies_type_condition(
+            &response_object,
+            &book,
+            &schema
+        ));
+    }
+
+    #[test]
+    fn type_condition_assumed_to_match_without_a_typename_to_check() {
+        let schema = schema();
+        let book = Name::new("Book").unwrap();
+        let response_object = object(serde_json::json!({ "title": "Dune" }));
+        assert!(object_satisfies_type_condition(
+            &response_object,
+            &book,
+            &schema
+        ));
+    }
+}